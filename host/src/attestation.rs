@@ -0,0 +1,184 @@
+// host/src/attestation.rs
+//! Signs a receipt's journal into a compact `header.payload.signature`
+//! attestation, binding a DID issuer to it. Keys are Ed25519 or RSA, PEM or DER.
+
+use anyhow::{ensure, Context};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ed25519_dalek::{Signer as _, Verifier as _};
+use pkcs8::{DecodePrivateKey, DecodePublicKey};
+use rsa::pkcs1v15::{Signature as RsaSignature, SigningKey as RsaSigningKey, VerifyingKey as RsaVerifyingKey};
+use rsa::signature::{Signer as _, Verifier as _};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::Path;
+
+use crate::merkle;
+use crate::redact::Replacement;
+
+#[derive(Serialize, Deserialize)]
+pub struct Claims {
+    pub iss: String,
+    pub iat: u64,
+    pub full_root: String,
+    pub redacted_root: String,
+    pub redacted_replacements: Vec<Replacement>,
+    pub method_id: [u32; 8],
+}
+
+impl Claims {
+    pub fn new(
+        issuer: &str,
+        issued_at: u64,
+        full_root: merkle::Hash,
+        redacted_root: merkle::Hash,
+        redacted_replacements: &[Replacement],
+        method_id: [u32; 8],
+    ) -> Self {
+        Self {
+            iss: issuer.to_string(),
+            iat: issued_at,
+            full_root: hex::encode(full_root),
+            redacted_root: hex::encode(redacted_root),
+            redacted_replacements: redacted_replacements.to_vec(),
+            method_id,
+        }
+    }
+}
+
+enum SigningKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    Rsa(Box<RsaSigningKey<Sha256>>),
+}
+
+/// Loads an Ed25519 or RSA private key from a PEM or DER file, trying each
+/// combination until one parses.
+fn load_signing_key(path: &Path) -> anyhow::Result<SigningKey> {
+    let pem = std::fs::read_to_string(path);
+    let der = std::fs::read(path)?;
+
+    if let Ok(pem) = &pem {
+        if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_pem(pem) {
+            return Ok(SigningKey::Ed25519(key));
+        }
+        if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(pem) {
+            return Ok(SigningKey::Rsa(Box::new(RsaSigningKey::new(key))));
+        }
+    }
+    if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_der(&der) {
+        return Ok(SigningKey::Ed25519(key));
+    }
+    if let Ok(key) = RsaPrivateKey::from_pkcs8_der(&der) {
+        return Ok(SigningKey::Rsa(Box::new(RsaSigningKey::new(key))));
+    }
+
+    anyhow::bail!(
+        "Could not parse {} as an Ed25519 or RSA private key (PEM/DER)",
+        path.display()
+    )
+}
+
+fn alg_name(key: &SigningKey) -> &'static str {
+    match key {
+        SigningKey::Ed25519(_) => "EdDSA",
+        SigningKey::Rsa(_) => "RS256",
+    }
+}
+
+/// Signs `claims` with the private key at `key_path`, returning the compact
+/// `header.payload.signature` attestation string.
+pub fn sign(key_path: &Path, claims: &Claims) -> anyhow::Result<String> {
+    let key = load_signing_key(key_path)?;
+
+    let header = serde_json::json!({ "alg": alg_name(&key), "typ": "ZKCAT-VC" });
+    let header_b64 = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?);
+    let payload_b64 = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?);
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+    let signature_b64 = match &key {
+        SigningKey::Ed25519(key) => {
+            URL_SAFE_NO_PAD.encode(key.sign(signing_input.as_bytes()).to_bytes())
+        }
+        SigningKey::Rsa(key) => {
+            URL_SAFE_NO_PAD.encode(key.sign(signing_input.as_bytes()).to_bytes())
+        }
+    };
+
+    Ok(format!("{}.{}", signing_input, signature_b64))
+}
+
+pub struct VerifiedAttestation {
+    pub claims: Claims,
+    pub verification_method: String,
+}
+
+/// Verifies a compact attestation's signature against the issuer's public
+/// key and returns its decoded claims.
+pub fn verify(attestation: &str, public_key_path: &Path) -> anyhow::Result<VerifiedAttestation> {
+    let mut parts = attestation.split('.');
+    let (header_b64, payload_b64, signature_b64) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(p), Some(s)) => (h, p, s),
+        _ => anyhow::bail!("Attestation is not in header.payload.signature form"),
+    };
+
+    let header: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(header_b64)?)?;
+    let alg = header["alg"].as_str().context("Attestation header is missing `alg`")?;
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+    let signature_bytes = URL_SAFE_NO_PAD.decode(signature_b64)?;
+
+    let pem = std::fs::read_to_string(public_key_path);
+    let der = std::fs::read(public_key_path)?;
+
+    match alg {
+        "EdDSA" => {
+            let key = pem
+                .ok()
+                .and_then(|pem| ed25519_dalek::VerifyingKey::from_public_key_pem(&pem).ok())
+                .or_else(|| ed25519_dalek::VerifyingKey::from_public_key_der(&der).ok())
+                .ok_or_else(|| anyhow::anyhow!("Could not parse Ed25519 public key"))?;
+            let signature = ed25519_dalek::Signature::from_slice(&signature_bytes)?;
+            key.verify(signing_input.as_bytes(), &signature)
+                .map_err(|_| anyhow::anyhow!("Attestation signature is invalid"))?;
+        }
+        "RS256" => {
+            let key = pem
+                .ok()
+                .and_then(|pem| RsaPublicKey::from_public_key_pem(&pem).ok())
+                .or_else(|| RsaPublicKey::from_public_key_der(&der).ok())
+                .ok_or_else(|| anyhow::anyhow!("Could not parse RSA public key"))?;
+            let verifying_key = RsaVerifyingKey::<Sha256>::new(key);
+            let signature = RsaSignature::try_from(signature_bytes.as_slice())?;
+            verifying_key
+                .verify(signing_input.as_bytes(), &signature)
+                .map_err(|_| anyhow::anyhow!("Attestation signature is invalid"))?;
+        }
+        other => anyhow::bail!("Unsupported attestation algorithm: {}", other),
+    }
+
+    let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload_b64)?)?;
+    Ok(VerifiedAttestation {
+        verification_method: public_key_path.display().to_string(),
+        claims,
+    })
+}
+
+/// Like [`verify`], but also checks the claims are bound to this proof's
+/// journal rather than lifted from an unrelated one.
+pub fn verify_bound(
+    attestation: &str,
+    public_key_path: &Path,
+    full_root: merkle::Hash,
+    redacted_root: merkle::Hash,
+    replacements: &[Replacement],
+    method_id: [u32; 8],
+) -> anyhow::Result<VerifiedAttestation> {
+    let verified = verify(attestation, public_key_path)?;
+    ensure!(
+        verified.claims.full_root == hex::encode(full_root)
+            && verified.claims.redacted_root == hex::encode(redacted_root)
+            && verified.claims.redacted_replacements == replacements
+            && verified.claims.method_id == method_id,
+        "Attestation claims do not match this proof's journal"
+    );
+    Ok(verified)
+}
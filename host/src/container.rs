@@ -0,0 +1,114 @@
+// host/src/container.rs
+//! Self-describing, versioned `.zkproof` file format: a magic prefix, an
+//! explicit format version, and the `METHOD_ID` the proof was built against,
+//! wrapping a `SavedProof`.
+
+use anyhow::{ensure, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::SavedProof;
+
+pub const MAGIC: &[u8; 5] = b"ZKCAT";
+pub const CURRENT_FORMAT_VERSION: u16 = 1;
+
+/// Commitment scheme the receipt's journal was built with.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitmentScheme {
+    MerkleSha256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct ProofMetadata {
+    pub original_filename: Option<String>,
+    pub created_at_unix: Option<u64>,
+    pub tool_version: Option<String>,
+}
+
+/// Everything after the `MAGIC` + `format_version` header.
+#[derive(Serialize, Deserialize)]
+struct ProofBody {
+    method_id: [u32; 8],
+    commitment_scheme: CommitmentScheme,
+    metadata: ProofMetadata,
+    proof: SavedProof,
+}
+
+pub struct ProofContainer {
+    pub format_version: u16,
+    pub method_id: [u32; 8],
+    pub commitment_scheme: CommitmentScheme,
+    pub metadata: ProofMetadata,
+    pub proof: SavedProof,
+}
+
+impl ProofContainer {
+    pub fn new(method_id: [u32; 8], metadata: ProofMetadata, proof: SavedProof) -> Self {
+        Self {
+            format_version: CURRENT_FORMAT_VERSION,
+            method_id,
+            commitment_scheme: CommitmentScheme::MerkleSha256,
+            metadata,
+            proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let body = ProofBody {
+            method_id: self.method_id,
+            commitment_scheme: self.commitment_scheme,
+            metadata: self.metadata.clone(),
+            proof: self.proof.clone(),
+        };
+
+        let mut out = Vec::with_capacity(MAGIC.len() + 2);
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.format_version.to_le_bytes());
+        out.extend_from_slice(&bincode::serialize(&body)?);
+        Ok(out)
+    }
+
+    /// Parses a `.zkproof` file, checking the magic prefix, format version,
+    /// and `method_id`. Falls back to the older headerless formats (a bare
+    /// `SavedProof` or `Receipt`), reporting them as version 0.
+    pub fn from_bytes(data: &[u8], expected_method_id: [u32; 8]) -> anyhow::Result<Self> {
+        if data.len() > MAGIC.len() + 2 && &data[..MAGIC.len()] == MAGIC {
+            let format_version = u16::from_le_bytes(data[MAGIC.len()..MAGIC.len() + 2].try_into()?);
+            ensure!(
+                format_version <= CURRENT_FORMAT_VERSION,
+                "Proof file is format version {}, but this build of zkcat only understands up to version {}",
+                format_version,
+                CURRENT_FORMAT_VERSION
+            );
+
+            let body: ProofBody = bincode::deserialize(&data[MAGIC.len() + 2..])
+                .context("Failed to deserialize proof container body")?;
+            ensure!(
+                body.method_id == expected_method_id,
+                "Proof was built against a different guest program (method ID mismatch); rebuild it with this version of zkcat"
+            );
+
+            return Ok(Self {
+                format_version,
+                method_id: body.method_id,
+                commitment_scheme: body.commitment_scheme,
+                metadata: body.metadata,
+                proof: body.proof,
+            });
+        }
+
+        let proof = bincode::deserialize::<SavedProof>(data)
+            .or_else(|_| {
+                bincode::deserialize::<risc0_zkvm::Receipt>(data)
+                    .map(|receipt| SavedProof { receipt, attestation: None })
+            })
+            .context("Failed to deserialize proof (not a recognized ZKCAT container, SavedProof, or raw receipt)")?;
+
+        Ok(Self {
+            format_version: 0,
+            method_id: expected_method_id,
+            commitment_scheme: CommitmentScheme::MerkleSha256,
+            metadata: ProofMetadata::default(),
+            proof,
+        })
+    }
+}
@@ -1,27 +1,92 @@
 // host/src/main.rs
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
 use risc0_zkvm::{default_prover, ExecutorEnv, Receipt};
-use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use anyhow::{Context, ensure};
 use methods::{METHOD_ELF, METHOD_ID};
 use serde_json::json;
 
+mod attestation;
+mod container;
+mod merkle;
+mod redact;
+mod transport;
+
+const REDACTED_MARKER: &str = redact::LINE_MARKER;
+
+/// The zk receipt plus an optional signed attestation, as stored in a `ProofContainer`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedProof {
+    pub receipt: Receipt,
+    pub attestation: Option<String>,
+}
+
+/// The redaction the guest is asked to apply and commit to.
+#[derive(Serialize, Deserialize, Clone)]
+struct RedactionRequest {
+    whole_lines: Vec<usize>,
+    ranges: Vec<redact::Replacement>,
+    regex_pattern: Option<String>,
+}
+
+/// Parses `L:START-END` into `(line, start, end)`, as accepted by `--redact-range`.
+fn parse_redact_range(spec: &str) -> anyhow::Result<redact::Replacement> {
+    let (line_str, cols) = spec
+        .split_once(':')
+        .with_context(|| format!("Invalid --redact-range '{}': expected L:START-END", spec))?;
+    let (start_str, end_str) = cols
+        .split_once('-')
+        .with_context(|| format!("Invalid --redact-range '{}': expected L:START-END", spec))?;
+    let line: usize = line_str.parse().with_context(|| format!("Invalid line number in '{}'", spec))?;
+    let start: usize = start_str.parse().with_context(|| format!("Invalid start column in '{}'", spec))?;
+    let end: usize = end_str.parse().with_context(|| format!("Invalid end column in '{}'", spec))?;
+    ensure!(start < end, "--redact-range '{}' has start >= end", spec);
+    Ok((line, start, end))
+}
+
 #[derive(Parser)]
 #[command(name = "zkcat")]
 #[command(about = "Zero-knowledge file viewer with redaction proofs", version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
 
+#[derive(Subcommand)]
+enum Commands {
+    /// View a file and generate a redaction proof
+    View(ViewArgs),
+    /// Verify a previously generated proof
+    Verify(VerifyArgs),
+    /// Reveal a single line with an authentication path into the redacted root
+    Disclose(DiscloseArgs),
+    /// Verify a line disclosure against a proof's committed root
+    VerifyDisclosure(VerifyDisclosureArgs),
+    /// Stream a redacted file and its proof to one peer over an encrypted channel
+    Serve(ServeArgs),
+    /// Fetch a redacted file and its proof from a `zkcat serve` peer
+    Fetch(FetchArgs),
+}
+
+#[derive(Parser)]
+struct ViewArgs {
     file: String,
-    
+
     #[arg(short, long)]
     redact: Option<String>,
-    
-    #[arg(short, long)]
-    verify: bool,
-    
+
+    /// Redact a substring match, e.g. an email pattern: `--redact-regex '\S+@\S+'`
+    #[arg(long)]
+    redact_regex: Option<String>,
+
+    /// Redact a column span on one line: `L:startcol-endcol` (0-based, end exclusive)
+    #[arg(long = "redact-range", value_name = "L:START-END")]
+    redact_ranges: Vec<String>,
+
     #[arg(short, long)]
     output: Option<String>,
 
@@ -30,70 +95,224 @@ struct Cli {
 
     #[arg(long)]
     stats: bool,
+
+    /// Private key (Ed25519 or RSA, PEM/DER) to sign the receipt's journal with
+    #[arg(long)]
+    sign_key: Option<PathBuf>,
+
+    /// DID of the issuer to embed in the signed attestation
+    #[arg(long, requires = "sign_key")]
+    issuer: Option<String>,
+}
+
+#[derive(Parser)]
+struct VerifyArgs {
+    /// Path to the `.zkproof` file
+    file: String,
+
+    #[arg(long)]
+    json: bool,
+
+    /// Public key matching --sign-key, to verify an embedded attestation
+    #[arg(long)]
+    issuer_key: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+struct DiscloseArgs {
+    /// Path to the original file (its `<file>.zkproof` must already exist)
+    file: String,
+
+    /// Zero-based line index to disclose
+    #[arg(short, long)]
+    line: usize,
+
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+#[derive(Parser)]
+struct VerifyDisclosureArgs {
+    /// Path to a disclosure JSON file produced by `zkcat disclose`
+    disclosure: String,
+
+    /// Path to the `.zkproof` file the disclosure claims to be rooted in
+    #[arg(long)]
+    proof: String,
+
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Parser)]
+struct ServeArgs {
+    file: String,
+
+    #[arg(short, long)]
+    redact: Option<String>,
+
+    /// Redact a substring match, e.g. an email pattern: `--redact-regex '\S+@\S+'`
+    #[arg(long)]
+    redact_regex: Option<String>,
+
+    /// Redact a column span on one line: `L:startcol-endcol` (0-based, end exclusive)
+    #[arg(long = "redact-range", value_name = "L:START-END")]
+    redact_ranges: Vec<String>,
+
+    /// Address to listen on, e.g. `0.0.0.0:7878`
+    #[arg(long)]
+    listen: String,
+
+    /// Long-term Ed25519 identity key (PEM/DER); a throwaway one is generated if omitted
+    #[arg(long)]
+    key: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+struct FetchArgs {
+    /// Address of a running `zkcat serve`, e.g. `127.0.0.1:7878`
+    addr: String,
+
+    /// Where to write the fetched redacted content
+    #[arg(long)]
+    out: String,
+
+    /// Long-term Ed25519 identity key (PEM/DER); a throwaway one is generated if omitted
+    #[arg(long)]
+    key: Option<PathBuf>,
+
+    /// Hex-encoded Ed25519 public key to pin the server's identity to
+    #[arg(long)]
+    peer_key: Option<String>,
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
-    
-    if args.verify {
-        verify_proof(&args.file, args.json)
-    } else {
-        view_and_prove(&args.file, args.redact, args.output, args.json, args.stats)
+
+    match args.command {
+        Commands::View(args) => view_and_prove(
+            &args.file,
+            args.redact,
+            args.redact_regex,
+            args.redact_ranges,
+            args.output,
+            args.json,
+            args.stats,
+            args.sign_key,
+            args.issuer,
+        ),
+        Commands::Verify(args) => verify_proof(&args.file, args.json, args.issuer_key),
+        Commands::Disclose(args) => disclose_line(&args.file, args.line, args.output),
+        Commands::VerifyDisclosure(args) => {
+            verify_disclosure(&args.disclosure, &args.proof, args.json)
+        }
+        Commands::Serve(args) => serve_command(
+            &args.file,
+            args.redact,
+            args.redact_regex,
+            args.redact_ranges,
+            &args.listen,
+            args.key,
+        ),
+        Commands::Fetch(args) => fetch_command(&args.addr, &args.out, args.key, args.peer_key),
+    }
+}
+
+/// Renders one line for the terminal preview, green with masked spans in red.
+fn render_line_colored(line: &str, line_index: usize, replacements: &[redact::Replacement]) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let spans: Vec<(usize, usize)> = replacements
+        .iter()
+        .filter(|&&(l, _, _)| l == line_index)
+        .map(|&(_, start, end)| (start, end))
+        .collect();
+
+    if spans.is_empty() {
+        return line.green().to_string();
+    }
+    if spans.len() == 1 && spans[0] == (0, chars.len()) {
+        return redact::LINE_MARKER.red().to_string();
+    }
+
+    let mut out = String::new();
+    let mut cursor = 0;
+    for (start, end) in spans {
+        out.push_str(&chars[cursor..start].iter().collect::<String>().green().to_string());
+        out.push_str(&redact::SPAN_MARKER.red().to_string());
+        cursor = end;
     }
+    out.push_str(&chars[cursor..].iter().collect::<String>().green().to_string());
+    out
 }
 
 fn view_and_prove(
-    file_path: &str, 
-    redact_lines: Option<String>, 
+    file_path: &str,
+    redact_lines: Option<String>,
+    redact_regex: Option<String>,
+    redact_range_specs: Vec<String>,
     output_file: Option<String>,
     json_output: bool,
-    show_stats: bool
+    show_stats: bool,
+    sign_key: Option<PathBuf>,
+    issuer: Option<String>,
 ) -> anyhow::Result<()> {
     let start_time = Instant::now();
-    
+
     let content = fs::read_to_string(file_path)
         .context("Failed to read input file")?;
 
-    let redact_indices: Vec<usize> = redact_lines
+    let whole_lines: Vec<usize> = redact_lines
         .map(|s| s.split(',').filter_map(|n| n.parse().ok()).collect())
         .unwrap_or_default();
+    let ranges: Vec<redact::Replacement> = redact_range_specs
+        .iter()
+        .map(|spec| parse_redact_range(spec))
+        .collect::<anyhow::Result<_>>()?;
 
-    let mut redacted_lines = Vec::new();
-    for (i, line) in content.lines().enumerate() {
-        if redact_indices.contains(&i) {
-            redacted_lines.push("***REDACTED***".to_string());
-        } else {
-            redacted_lines.push(line.to_string());
-        }
-    }
+    let lines: Vec<&str> = content.lines().collect();
+
+    let (redacted_lines, preview_replacements) =
+        redact::apply(&lines, &whole_lines, &ranges, redact_regex.as_deref())?;
     let redacted_content = redacted_lines.join("\n");
 
     if !json_output {
-        for (i, line) in content.lines().enumerate() {
-            if redact_indices.contains(&i) {
-                println!("{}", "***REDACTED***".red());
-            } else {
-                println!("{}", line.green());
-            }
+        for (i, line) in lines.iter().enumerate() {
+            println!("{}", render_line_colored(line, i, &preview_replacements));
         }
     }
 
     if let Some(output_path) = &output_file {
         fs::write(output_path, &redacted_content)
             .context("Failed to write redacted content to output file")?;
-        
+
         if !json_output {
             println!("\nRedacted content saved to: {}", output_path);
         }
     }
 
-    let full_hash = Sha256::digest(content.as_bytes());
-    let full_hash_array: [u8; 32] = full_hash.into();
+    let full_leaves = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| merkle::leaf_hash(i, line))
+        .collect();
+    let full_root = merkle::root(&merkle::build_tree(full_leaves));
+
+    let redacted_leaves = redacted_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| merkle::leaf_hash(i, line))
+        .collect();
+    let redacted_root = merkle::root(&merkle::build_tree(redacted_leaves));
 
     let proof_start = Instant::now();
 
+    let request = RedactionRequest {
+        whole_lines,
+        ranges,
+        regex_pattern: redact_regex,
+    };
     let env = ExecutorEnv::builder()
-        .write(&(content.clone(), redact_indices.clone()))?
+        .write(&(content.clone(), request))?
         .build()?;
 
     let prover = default_prover();
@@ -109,20 +328,48 @@ fn view_and_prove(
         .context("Proof verification failed")?;
     let verify_time = verify_start.elapsed();
 
-    let (journal_full, journal_redacted, journal_indices): ([u8; 32], [u8; 32], Vec<usize>) =
+    let (journal_full_root, journal_redacted_root, journal_replacements): (merkle::Hash, merkle::Hash, Vec<redact::Replacement>) =
         receipt.journal.decode()?;
 
     ensure!(
-        journal_full == full_hash_array,
-        "Full file hash mismatch between host and guest"
+        journal_full_root == full_root,
+        "Full file Merkle root mismatch between host and guest"
+    );
+    ensure!(
+        journal_redacted_root == redacted_root,
+        "Redacted Merkle root mismatch between host and guest"
     );
     ensure!(
-        journal_indices == redact_indices,
-        "Redaction indices mismatch between host and guest"
+        journal_replacements == preview_replacements,
+        "Redaction replacements mismatch between host and guest"
     );
 
+    let attestation = match (&sign_key, &issuer) {
+        (Some(key_path), Some(issuer_did)) => {
+            let issued_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let claims = attestation::Claims::new(
+                issuer_did,
+                issued_at,
+                journal_full_root,
+                journal_redacted_root,
+                &journal_replacements,
+                METHOD_ID,
+            );
+            Some(attestation::sign(key_path, &claims).context("Failed to sign attestation")?)
+        }
+        (Some(_), None) => anyhow::bail!("--sign-key requires --issuer"),
+        (None, _) => None,
+    };
+
     let proof_file = format!("{}.zkproof", file_path);
-    fs::write(&proof_file, bincode::serialize(&receipt)?)
+    let saved_proof = SavedProof { receipt, attestation };
+    let metadata = container::ProofMetadata {
+        original_filename: Some(file_path.to_string()),
+        created_at_unix: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+        tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+    };
+    let container = container::ProofContainer::new(METHOD_ID, metadata, saved_proof.clone());
+    fs::write(&proof_file, container.to_bytes()?)
         .context("Failed to save proof file")?;
 
     let total_time = start_time.elapsed();
@@ -130,75 +377,333 @@ fn view_and_prove(
     if json_output {
         let result = json!({
             "success": true,
-            "full_file_hash": hex::encode(journal_full),
-            "redacted_file_hash": hex::encode(journal_redacted),
-            "redacted_line_indices": journal_indices,
+            "full_root": hex::encode(journal_full_root),
+            "redacted_root": hex::encode(journal_redacted_root),
+            "replacements": journal_replacements,
             "proof_file": proof_file,
             "output_file": output_file,
+            "signed": saved_proof.attestation.is_some(),
             "statistics": show_stats.then(|| json!({
                 "total_time_ms": total_time.as_millis(),
                 "proof_generation_time_ms": proof_time.as_millis(),
                 "verification_time_ms": verify_time.as_millis(),
                 "file_size_bytes": content.len(),
-                "lines_total": content.lines().count(),
-                "lines_redacted": redact_indices.len()
+                "lines_total": lines.len(),
+                "spans_redacted": journal_replacements.len()
             }))
         });
         println!("{}", serde_json::to_string_pretty(&result)?);
     } else {
         println!("\n{} Proof generated and verified!", "✓".green());
-        println!("- Full file SHA-256 hash: {}", hex::encode(journal_full));
-        println!("- Redacted file SHA-256 hash: {}", hex::encode(journal_redacted));
-        println!("- Redacted line indices: {:?}", journal_indices);
+        println!("- Full file Merkle root: {}", hex::encode(journal_full_root));
+        println!("- Redacted Merkle root: {}", hex::encode(journal_redacted_root));
+        println!("- Replacements (line, start, end): {:?}", journal_replacements);
         println!("Proof saved to: {}", proof_file);
-        
+        if let Some(issuer_did) = &issuer {
+            println!("- Signed attestation embedded for issuer: {}", issuer_did);
+        }
+
         if show_stats {
             println!("\n{} Statistics:", "📊".blue());
             println!("- Total time: {:.2}s", total_time.as_secs_f64());
             println!("- Proof generation: {:.2}s", proof_time.as_secs_f64());
             println!("- Verification: {:.3}s", verify_time.as_secs_f64());
             println!("- File size: {} bytes", content.len());
-            println!("- Total lines: {}", content.lines().count());
-            println!("- Redacted lines: {}", redact_indices.len());
+            println!("- Total lines: {}", lines.len());
+            println!("- Redacted spans: {}", journal_replacements.len());
         }
     }
 
     Ok(())
 }
 
-fn verify_proof(proof_path: &str, json_output: bool) -> anyhow::Result<()> {
+fn verify_proof(proof_path: &str, json_output: bool, issuer_key: Option<PathBuf>) -> anyhow::Result<()> {
     let start_time = Instant::now();
-    
+
     let proof_data = fs::read(proof_path)
         .context("Failed to read proof file")?;
-    let receipt: Receipt = bincode::deserialize(&proof_data)
-        .context("Failed to deserialize proof")?;
+    let saved_proof = container::ProofContainer::from_bytes(&proof_data, METHOD_ID)?.proof;
 
-    receipt.verify(METHOD_ID)
+    saved_proof.receipt.verify(METHOD_ID)
         .context("Proof verification failed")?;
 
     let verify_time = start_time.elapsed();
 
-    let (full_hash, redacted_hash, indices): ([u8; 32], [u8; 32], Vec<usize>) =
-        receipt.journal.decode()?;
+    let (full_root, redacted_root, replacements): (merkle::Hash, merkle::Hash, Vec<redact::Replacement>) =
+        saved_proof.receipt.journal.decode()?;
+
+    let attestation = match (&saved_proof.attestation, &issuer_key) {
+        (Some(attestation), Some(key_path)) => {
+            match attestation::verify_bound(attestation, key_path, full_root, redacted_root, &replacements, METHOD_ID) {
+                Ok(verified) => Some((Some(verified.claims.iss), true, Some(verified.verification_method), None)),
+                Err(err) => Some((None, false, None, Some(err.to_string()))),
+            }
+        }
+        (Some(_), None) => None,
+        (None, _) => None,
+    };
 
     if json_output {
-        let result = json!({
+        let mut result = json!({
             "success": true,
             "verified": true,
-            "full_file_hash": hex::encode(full_hash),
-            "redacted_file_hash": hex::encode(redacted_hash),
-            "redacted_line_indices": indices,
+            "full_root": hex::encode(full_root),
+            "redacted_root": hex::encode(redacted_root),
+            "replacements": replacements,
             "verification_time_ms": verify_time.as_millis()
         });
+        if let Some((issuer, signature_valid, verification_method, error)) = &attestation {
+            result["signature_valid"] = json!(signature_valid);
+            result["issuer"] = json!(issuer);
+            result["verification_method"] = json!(verification_method);
+            if let Some(error) = error {
+                result["attestation_error"] = json!(error);
+            }
+        }
         println!("{}", serde_json::to_string_pretty(&result)?);
     } else {
         println!("{} Proof verified successfully!", "✓".green());
-        println!("- Full file SHA-256 hash: {}", hex::encode(full_hash));
-        println!("- Redacted file SHA-256 hash: {}", hex::encode(redacted_hash));
-        println!("- Redacted line indices: {:?}", indices);
+        println!("- Full file Merkle root: {}", hex::encode(full_root));
+        println!("- Redacted Merkle root: {}", hex::encode(redacted_root));
+        println!("- Replacements (line, start, end): {:?}", replacements);
         println!("- Verification time: {:.3}s", verify_time.as_secs_f64());
+        match &attestation {
+            Some((Some(issuer), true, Some(verification_method), _)) => {
+                println!("- Issuer: {} (signature valid: true, via {})", issuer, verification_method);
+            }
+            Some((_, false, _, error)) => {
+                println!(
+                    "{} Attestation present but invalid: {}",
+                    "✗".red(),
+                    error.as_deref().unwrap_or("unknown error")
+                );
+            }
+            _ => {
+                if saved_proof.attestation.is_some() {
+                    println!("- Attestation present but not checked (pass --issuer-key to verify it)");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reveals one line with its authentication path into the committed
+/// `redacted_root`. A redacted line discloses as its redaction marker, since
+/// that's what the redacted tree actually commits to at that position.
+fn disclose_line(file_path: &str, line: usize, output_file: Option<String>) -> anyhow::Result<()> {
+    let proof_path = format!("{}.zkproof", file_path);
+    let proof_data = fs::read(&proof_path)
+        .with_context(|| format!("Failed to read proof file {} (run `zkcat view` first)", proof_path))?;
+    let saved_proof = container::ProofContainer::from_bytes(&proof_data, METHOD_ID)?.proof;
+    saved_proof.receipt.verify(METHOD_ID)
+        .context("Proof verification failed")?;
+
+    let (_full_root, redacted_root, replacements): (merkle::Hash, merkle::Hash, Vec<redact::Replacement>) =
+        saved_proof.receipt.journal.decode()?;
+
+    let content = fs::read_to_string(file_path)
+        .context("Failed to read input file")?;
+    let lines: Vec<&str> = content.lines().collect();
+    ensure!(
+        line < lines.len(),
+        "Line index {} out of range ({} lines)",
+        line,
+        lines.len()
+    );
+
+    let redacted_lines = redact::render(&lines, &replacements);
+    let redacted_leaves = redacted_lines
+        .iter()
+        .enumerate()
+        .map(|(i, l)| merkle::leaf_hash(i, l))
+        .collect();
+    let tree = merkle::build_tree(redacted_leaves);
+    let recomputed_root = merkle::root(&tree);
+    ensure!(
+        recomputed_root == redacted_root,
+        "Recomputed redacted root does not match the proof; has the file changed?"
+    );
+
+    let disclosed_text = redacted_lines[line].clone();
+    let auth_path = merkle::auth_path(&tree, line);
+
+    let result = json!({
+        "index": line,
+        "text": disclosed_text,
+        "auth_path": auth_path.iter().map(|(hash, sibling_is_left)| json!({
+            "hash": hex::encode(hash),
+            "sibling_is_left": sibling_is_left,
+        })).collect::<Vec<_>>(),
+        "root": hex::encode(redacted_root),
+    });
+    let result_pretty = serde_json::to_string_pretty(&result)?;
+
+    if let Some(output_path) = &output_file {
+        fs::write(output_path, &result_pretty)
+            .context("Failed to write disclosure to output file")?;
+        println!("{} Disclosure for line {} saved to: {}", "✓".green(), line, output_path);
+    } else {
+        println!("{}", result_pretty);
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Recomputes the Merkle root from a disclosure and checks it against the
+/// `redacted_root` in `proof_path`'s receipt journal.
+fn verify_disclosure(disclosure_path: &str, proof_path: &str, json_output: bool) -> anyhow::Result<()> {
+    let disclosure_data = fs::read_to_string(disclosure_path)
+        .context("Failed to read disclosure file")?;
+    let disclosure: serde_json::Value = serde_json::from_str(&disclosure_data)
+        .context("Failed to parse disclosure JSON")?;
+
+    let index = disclosure["index"]
+        .as_u64()
+        .context("Disclosure is missing an `index` field")? as usize;
+    let text = disclosure["text"]
+        .as_str()
+        .context("Disclosure is missing a `text` field")?;
+    let auth_path: Vec<(merkle::Hash, bool)> = disclosure["auth_path"]
+        .as_array()
+        .context("Disclosure is missing an `auth_path` field")?
+        .iter()
+        .map(|step| -> anyhow::Result<(merkle::Hash, bool)> {
+            let hash_hex = step["hash"].as_str().context("auth_path entry missing `hash`")?;
+            let sibling_is_left = step["sibling_is_left"].as_bool().context("auth_path entry missing `sibling_is_left`")?;
+            let hash_bytes = hex::decode(hash_hex).context("auth_path hash is not valid hex")?;
+            let hash: merkle::Hash = hash_bytes.try_into()
+                .map_err(|_| anyhow::anyhow!("auth_path hash is not 32 bytes"))?;
+            Ok((hash, sibling_is_left))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let proof_data = fs::read(proof_path)
+        .context("Failed to read proof file")?;
+    let saved_proof = container::ProofContainer::from_bytes(&proof_data, METHOD_ID)?.proof;
+    saved_proof.receipt.verify(METHOD_ID)
+        .context("Proof verification failed")?;
+
+    let (_full_root, redacted_root, _replacements): (merkle::Hash, merkle::Hash, Vec<redact::Replacement>) =
+        saved_proof.receipt.journal.decode()?;
+
+    let leaf = merkle::leaf_hash(index, text);
+    let recomputed_root = merkle::verify_path(leaf, &auth_path);
+    ensure!(
+        recomputed_root == redacted_root,
+        "Disclosure does not match the proof's committed redacted root"
+    );
+
+    if json_output {
+        let result = json!({
+            "success": true,
+            "verified": true,
+            "index": index,
+            "text": text,
+            "root": hex::encode(redacted_root),
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!("{} Disclosure verified against the committed redacted root!", "✓".green());
+        println!("- Line {}: {:?}", index, text);
+        println!("- Root: {}", hex::encode(redacted_root));
+    }
+
+    Ok(())
+}
+
+/// Proves the redaction in-process and streams it plus the proof to the
+/// first peer that connects.
+fn serve_command(
+    file_path: &str,
+    redact_lines: Option<String>,
+    redact_regex: Option<String>,
+    redact_range_specs: Vec<String>,
+    listen_addr: &str,
+    key_path: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let content = fs::read_to_string(file_path)
+        .context("Failed to read input file")?;
+    let whole_lines: Vec<usize> = redact_lines
+        .map(|s| s.split(',').filter_map(|n| n.parse().ok()).collect())
+        .unwrap_or_default();
+    let ranges: Vec<redact::Replacement> = redact_range_specs
+        .iter()
+        .map(|spec| parse_redact_range(spec))
+        .collect::<anyhow::Result<_>>()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let (redacted_lines, _replacements) = redact::apply(&lines, &whole_lines, &ranges, redact_regex.as_deref())?;
+    let redacted_content = redacted_lines.join("\n");
+
+    let request = RedactionRequest { whole_lines, ranges, regex_pattern: redact_regex };
+    let env = ExecutorEnv::builder()
+        .write(&(content.clone(), request))?
+        .build()?;
+    let prover = default_prover();
+    let prove_info = prover.prove(env, METHOD_ELF)
+        .context("Proof generation failed")?;
+    let receipt = prove_info.receipt;
+    receipt.verify(METHOD_ID)
+        .context("Proof verification failed")?;
+
+    let saved_proof = SavedProof { receipt, attestation: None };
+    let metadata = container::ProofMetadata {
+        original_filename: Some(file_path.to_string()),
+        created_at_unix: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+        tool_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+    };
+    let proof_bytes = container::ProofContainer::new(METHOD_ID, metadata, saved_proof).to_bytes()?;
+
+    let identity = transport::Identity::load_or_generate(key_path.as_deref())?;
+    println!("Identity (share with the fetcher as --peer-key): {}", transport::fingerprint(&identity.public_key()));
+    println!("Listening on {} ...", listen_addr);
+
+    transport::serve_once(listen_addr, &identity, &proof_bytes, &redacted_content)?;
+    println!("{} Streamed redacted content and proof to peer", "✓".green());
+    Ok(())
+}
+
+/// Fetches a redacted file and proof from a `zkcat serve` peer, verifying
+/// the receipt and the redacted root before writing anything to disk.
+fn fetch_command(
+    addr: &str,
+    out_path: &str,
+    key_path: Option<PathBuf>,
+    peer_key: Option<String>,
+) -> anyhow::Result<()> {
+    let identity = transport::Identity::load_or_generate(key_path.as_deref())?;
+    let pinned_peer_key = peer_key.as_deref().map(transport::parse_peer_key).transpose()?;
+
+    let (proof_bytes, redacted_content) = transport::fetch_once(addr, &identity, pinned_peer_key)?;
+
+    let saved_proof = container::ProofContainer::from_bytes(&proof_bytes, METHOD_ID)
+        .context("Failed to deserialize proof received from peer")?
+        .proof;
+    saved_proof.receipt.verify(METHOD_ID)
+        .context("Proof verification failed")?;
+    let (_full_root, redacted_root, _replacements): (merkle::Hash, merkle::Hash, Vec<redact::Replacement>) =
+        saved_proof.receipt.journal.decode()?;
+
+    let leaves = redacted_content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| merkle::leaf_hash(i, line))
+        .collect();
+    let recomputed_root = merkle::root(&merkle::build_tree(leaves));
+    ensure!(
+        recomputed_root == redacted_root,
+        "Fetched content does not match the proof's committed redacted root"
+    );
+
+    fs::write(out_path, &redacted_content)
+        .context("Failed to write fetched content")?;
+    let proof_out_path = format!("{}.zkproof", out_path);
+    fs::write(&proof_out_path, &proof_bytes)
+        .context("Failed to write fetched proof")?;
+
+    println!("{} Fetched and verified content from {}", "✓".green(), addr);
+    println!("- Redacted Merkle root: {}", hex::encode(redacted_root));
+    println!("- Saved to: {} (+ {})", out_path, proof_out_path);
+    Ok(())
+}
@@ -0,0 +1,132 @@
+// host/src/merkle.rs
+//! Binary Merkle tree over per-line leaves, for selective line disclosure.
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+/// Domain-separation tags (RFC 6962-style) so a leaf hash can't be passed
+/// off as an internal node hash, or vice versa.
+const LEAF_TAG: u8 = 0x00;
+const NODE_TAG: u8 = 0x01;
+
+/// `leaf_i = SHA256(0x00 ++ i_as_le_bytes ++ line_i)`.
+pub fn leaf_hash(index: usize, line: &str) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_TAG]);
+    hasher.update((index as u64).to_le_bytes());
+    hasher.update(line.as_bytes());
+    hasher.finalize().into()
+}
+
+fn parent_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Builds every level of the tree over `leaves`, duplicating the last node
+/// of a level when its length is odd. `levels[0]` holds the leaves
+/// themselves and `levels.last()` holds the single-element root.
+pub fn build_tree(mut leaves: Vec<Hash>) -> Vec<Vec<Hash>> {
+    if leaves.is_empty() {
+        leaves.push(leaf_hash(0, ""));
+    }
+
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let current = levels.last().unwrap();
+        let mut next = Vec::with_capacity(current.len().div_ceil(2));
+        for pair in current.chunks(2) {
+            let right = pair.get(1).unwrap_or(&pair[0]);
+            next.push(parent_hash(&pair[0], right));
+        }
+        levels.push(next);
+    }
+    levels
+}
+
+pub fn root(levels: &[Vec<Hash>]) -> Hash {
+    levels.last().unwrap()[0]
+}
+
+/// Sibling hash at each level on the path from `index`'s leaf up to the
+/// root, bottom-to-top, alongside whether that sibling is the left node of
+/// the pair (needed to recombine the pair in the right order).
+pub fn auth_path(levels: &[Vec<Hash>], mut index: usize) -> Vec<(Hash, bool)> {
+    let mut path = Vec::with_capacity(levels.len() - 1);
+    for level in &levels[..levels.len() - 1] {
+        let sibling_index = if index % 2 == 0 { index + 1 } else { index - 1 };
+        let sibling = level.get(sibling_index).copied().unwrap_or(level[index]);
+        path.push((sibling, index % 2 == 1));
+        index /= 2;
+    }
+    path
+}
+
+/// Recomputes a Merkle root from a leaf hash and its authentication path.
+pub fn verify_path(leaf: Hash, path: &[(Hash, bool)]) -> Hash {
+    path.iter().fold(leaf, |acc, (sibling, sibling_is_left)| {
+        if *sibling_is_left {
+            parent_hash(sibling, &acc)
+        } else {
+            parent_hash(&acc, sibling)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_of(lines: &[&str]) -> Vec<Vec<Hash>> {
+        let leaves = lines.iter().enumerate().map(|(i, l)| leaf_hash(i, l)).collect();
+        build_tree(leaves)
+    }
+
+    #[test]
+    fn auth_path_round_trips_for_every_leaf_even_count() {
+        let lines = ["alice", "bob", "carol", "dave"];
+        let levels = tree_of(&lines);
+        let expected_root = root(&levels);
+        for (i, line) in lines.iter().enumerate() {
+            let path = auth_path(&levels, i);
+            assert_eq!(verify_path(leaf_hash(i, line), &path), expected_root);
+        }
+    }
+
+    #[test]
+    fn auth_path_round_trips_with_odd_count() {
+        let lines = ["alice", "bob", "carol"];
+        let levels = tree_of(&lines);
+        let expected_root = root(&levels);
+        for (i, line) in lines.iter().enumerate() {
+            let path = auth_path(&levels, i);
+            assert_eq!(verify_path(leaf_hash(i, line), &path), expected_root);
+        }
+    }
+
+    #[test]
+    fn single_leaf_tree_has_empty_auth_path() {
+        let levels = tree_of(&["only"]);
+        let path = auth_path(&levels, 0);
+        assert!(path.is_empty());
+        assert_eq!(verify_path(leaf_hash(0, "only"), &path), root(&levels));
+    }
+
+    #[test]
+    fn empty_file_hashes_to_the_sentinel_leaf() {
+        let levels = tree_of(&[]);
+        assert_eq!(root(&levels), leaf_hash(0, ""));
+    }
+
+    #[test]
+    fn leaf_and_internal_node_hashes_cannot_collide() {
+        let left = leaf_hash(0, "a");
+        let right = leaf_hash(1, "b");
+        let internal = parent_hash(&left, &right);
+        assert_ne!(internal, leaf_hash(0, "ab"));
+    }
+}
@@ -0,0 +1,195 @@
+// host/src/redact.rs
+//! Resolves whole-line, range, and regex redactions into a merged list of
+//! `(line, start, end)` spans. Kept in sync with `methods/guest/src/redact.rs`.
+
+use regex::Regex;
+
+/// Marker substituted for a span that covers an entire line.
+pub const LINE_MARKER: &str = "***REDACTED***";
+/// Marker substituted inline for a partial-line span.
+pub const SPAN_MARKER: &str = "[REDACTED]";
+
+/// A resolved, already-merged redaction span: `(line, start_char, end_char)`.
+pub type Replacement = (usize, usize, usize);
+
+/// Merges `whole_lines`, `ranges`, and `regex_pattern` matches into spans and
+/// redacts them, returning the redacted lines and the replacements made.
+pub fn apply(
+    lines: &[&str],
+    whole_lines: &[usize],
+    ranges: &[Replacement],
+    regex_pattern: Option<&str>,
+) -> anyhow::Result<(Vec<String>, Vec<Replacement>)> {
+    let compiled = regex_pattern.map(Regex::new).transpose()?;
+
+    let mut spans_by_line: Vec<Vec<(usize, usize)>> = vec![Vec::new(); lines.len()];
+
+    for &line in whole_lines {
+        if line < lines.len() {
+            spans_by_line[line].push((0, lines[line].chars().count()));
+        }
+    }
+    for &(line, start, end) in ranges {
+        if line < lines.len() {
+            let len = lines[line].chars().count();
+            if start < len {
+                spans_by_line[line].push((start, end.min(len)));
+            }
+        }
+    }
+    if let Some(re) = &compiled {
+        for (i, line) in lines.iter().enumerate() {
+            if re.is_match(line) {
+                let byte_to_char = byte_to_char_map(line);
+                for m in re.find_iter(line) {
+                    spans_by_line[i].push((byte_to_char[m.start()], byte_to_char[m.end()]));
+                }
+            }
+        }
+    }
+
+    let mut redacted_lines = Vec::with_capacity(lines.len());
+    let mut replacements = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let mut spans = std::mem::take(&mut spans_by_line[i]);
+        spans.sort_unstable();
+        let merged = merge_spans(spans);
+
+        if merged.is_empty() {
+            redacted_lines.push(line.to_string());
+            continue;
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        if merged.len() == 1 && merged[0] == (0, chars.len()) {
+            redacted_lines.push(LINE_MARKER.to_string());
+            replacements.push((i, 0, chars.len()));
+            continue;
+        }
+
+        redacted_lines.push(render_spans(&chars, &merged, SPAN_MARKER));
+        for (start, end) in merged {
+            replacements.push((i, start, end));
+        }
+    }
+
+    Ok((redacted_lines, replacements))
+}
+
+/// Re-renders `lines` from an already-resolved replacement list (as carried
+/// in a proof's journal), without needing the original ranges/regex spec.
+pub fn render(lines: &[&str], replacements: &[Replacement]) -> Vec<String> {
+    let mut spans_by_line: Vec<Vec<(usize, usize)>> = vec![Vec::new(); lines.len()];
+    for &(line, start, end) in replacements {
+        if line < lines.len() {
+            spans_by_line[line].push((start, end));
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let chars: Vec<char> = line.chars().collect();
+            let spans = &spans_by_line[i];
+            if spans.is_empty() {
+                line.to_string()
+            } else if spans.len() == 1 && spans[0] == (0, chars.len()) {
+                LINE_MARKER.to_string()
+            } else {
+                render_spans(&chars, spans, SPAN_MARKER)
+            }
+        })
+        .collect()
+}
+
+fn render_spans(chars: &[char], spans: &[(usize, usize)], marker: &str) -> String {
+    let mut out = String::new();
+    let mut cursor = 0;
+    for &(start, end) in spans {
+        out.extend(&chars[cursor..start]);
+        out.push_str(marker);
+        cursor = end;
+    }
+    out.extend(&chars[cursor..]);
+    out
+}
+
+/// Maps every byte offset in `line` (including the one-past-the-end offset)
+/// to its char index, so byte offsets from `Regex::find_iter` can be
+/// translated into the char offsets every other span in this module uses.
+fn byte_to_char_map(line: &str) -> Vec<usize> {
+    let mut map = vec![0; line.len() + 1];
+    let mut char_index = 0;
+    for (byte_index, ch) in line.char_indices() {
+        map[byte_index] = char_index;
+        char_index += 1;
+        for offset in 1..ch.len_utf8() {
+            map[byte_index + offset] = char_index;
+        }
+    }
+    map[line.len()] = char_index;
+    map
+}
+
+fn merge_spans(spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_ranges_merge_into_one_span() {
+        let lines = ["hello world"];
+        let ranges = [(0, 0, 5), (0, 3, 8)];
+        let (redacted, replacements) = apply(&lines, &[], &ranges, None).unwrap();
+        assert_eq!(redacted, vec!["[REDACTED]rld".to_string()]);
+        assert_eq!(replacements, vec![(0, 0, 8)]);
+    }
+
+    #[test]
+    fn whole_line_redaction_wins_over_partial_spans() {
+        let lines = ["hello world"];
+        let ranges = [(0, 0, 5)];
+        let (redacted, replacements) = apply(&lines, &[0], &ranges, None).unwrap();
+        assert_eq!(redacted, vec![LINE_MARKER.to_string()]);
+        assert_eq!(replacements, vec![(0, 0, "hello world".chars().count())]);
+    }
+
+    #[test]
+    fn regex_match_after_a_multibyte_char_uses_char_offsets() {
+        let lines = ["héllo bob@x.com"];
+        let (redacted, replacements) = apply(&lines, &[], &[], Some(r"\S+@\S+")).unwrap();
+        assert_eq!(redacted, vec!["héllo [REDACTED]".to_string()]);
+        let chars: Vec<char> = lines[0].chars().collect();
+        let (_, start, end) = replacements[0];
+        assert_eq!(chars[start..end].iter().collect::<String>(), "bob@x.com");
+    }
+
+    #[test]
+    fn range_starting_past_the_end_of_a_line_is_dropped_not_panicking() {
+        let lines = ["hi"];
+        let ranges = [(0, 5, 10)];
+        let (redacted, replacements) = apply(&lines, &[], &ranges, None).unwrap();
+        assert_eq!(redacted, vec!["hi".to_string()]);
+        assert!(replacements.is_empty());
+    }
+
+    #[test]
+    fn render_reproduces_apply_output_from_replacements_alone() {
+        let lines = ["hello world"];
+        let ranges = [(0, 0, 5)];
+        let (redacted, replacements) = apply(&lines, &[], &ranges, None).unwrap();
+        assert_eq!(render(&lines, &replacements), redacted);
+    }
+}
@@ -0,0 +1,246 @@
+// host/src/transport.rs
+//! Mutually authenticated, encrypted transport for `zkcat serve`/`fetch`:
+//! Ed25519 identities sign ephemeral X25519 keys, ECDH + HKDF-SHA256 derive
+//! a ChaCha20-Poly1305 key per direction, and every length-prefixed frame is
+//! encrypted and authenticated with it.
+
+use anyhow::{ensure, Context};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hkdf::Hkdf;
+use rand_core::OsRng;
+use sha2::Sha256;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use x25519_dalek::{EphemeralSecret, PublicKey as XPublicKey};
+
+const MAX_FRAME_LEN: u32 = 64 * 1024 * 1024;
+
+/// A party's long-term Ed25519 identity, used only to sign the ephemeral
+/// X25519 public key exchanged at handshake time.
+pub struct Identity {
+    signing_key: SigningKey,
+}
+
+impl Identity {
+    /// Loads a PEM/DER Ed25519 private key from `path`, or generates a
+    /// fresh throwaway identity if none is given.
+    pub fn load_or_generate(path: Option<&Path>) -> anyhow::Result<Self> {
+        use pkcs8::DecodePrivateKey;
+
+        let signing_key = match path {
+            Some(path) => {
+                let pem = std::fs::read_to_string(path);
+                let der = std::fs::read(path)?;
+                pem.ok()
+                    .and_then(|pem| SigningKey::from_pkcs8_pem(&pem).ok())
+                    .or_else(|| SigningKey::from_pkcs8_der(&der).ok())
+                    .with_context(|| format!("Could not parse {} as an Ed25519 key", path.display()))?
+            }
+            None => SigningKey::generate(&mut OsRng),
+        };
+        Ok(Self { signing_key })
+    }
+
+    pub fn public_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+}
+
+/// Derived per-direction channel after a successful handshake.
+struct SecureChannel {
+    stream: TcpStream,
+    send_cipher: ChaCha20Poly1305,
+    recv_cipher: ChaCha20Poly1305,
+    send_counter: u64,
+    recv_counter: u64,
+}
+
+fn nonce_for(counter: u64) -> Nonce {
+    let mut bytes = [0u8; 12];
+    bytes[4..].copy_from_slice(&counter.to_be_bytes());
+    Nonce::clone_from_slice(&bytes)
+}
+
+impl SecureChannel {
+    fn write_frame(&mut self, plaintext: &[u8]) -> anyhow::Result<()> {
+        let nonce = nonce_for(self.send_counter);
+        self.send_counter += 1;
+        let ciphertext = self
+            .send_cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt frame"))?;
+        let len = u32::try_from(ciphertext.len())?;
+        self.stream.write_all(&len.to_be_bytes())?;
+        self.stream.write_all(&ciphertext)?;
+        Ok(())
+    }
+
+    fn read_frame(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut len_bytes = [0u8; 4];
+        self.stream.read_exact(&mut len_bytes)?;
+        let len = u32::from_be_bytes(len_bytes);
+        ensure!(len <= MAX_FRAME_LEN, "Frame of {} bytes exceeds the {} byte limit", len, MAX_FRAME_LEN);
+
+        let mut ciphertext = vec![0u8; len as usize];
+        self.stream.read_exact(&mut ciphertext)?;
+
+        let nonce = nonce_for(self.recv_counter);
+        self.recv_counter += 1;
+        self.recv_cipher
+            .decrypt(&nonce, ciphertext.as_slice())
+            .map_err(|_| anyhow::anyhow!("Frame failed authentication"))
+    }
+}
+
+/// A signed ephemeral public key sent by each side at handshake time.
+struct HandshakeMessage {
+    identity_key: VerifyingKey,
+    ephemeral_key: XPublicKey,
+    signature: Signature,
+}
+
+impl HandshakeMessage {
+    const LEN: usize = 32 + 32 + 64;
+
+    fn new(identity: &Identity, ephemeral_public: &XPublicKey) -> Self {
+        let signature = identity.signing_key.sign(ephemeral_public.as_bytes());
+        Self {
+            identity_key: identity.public_key(),
+            ephemeral_key: *ephemeral_public,
+            signature,
+        }
+    }
+
+    fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        out[..32].copy_from_slice(self.identity_key.as_bytes());
+        out[32..64].copy_from_slice(self.ephemeral_key.as_bytes());
+        out[64..].copy_from_slice(&self.signature.to_bytes());
+        out
+    }
+
+    fn from_bytes(bytes: &[u8; Self::LEN]) -> anyhow::Result<Self> {
+        let identity_key = VerifyingKey::from_bytes(bytes[..32].try_into()?)
+            .context("Invalid peer identity key")?;
+        let ephemeral_key = XPublicKey::from(<[u8; 32]>::try_from(&bytes[32..64])?);
+        let signature = Signature::from_bytes(bytes[64..].try_into()?);
+        identity_key
+            .verify(ephemeral_key.as_bytes(), &signature)
+            .context("Peer handshake signature is invalid")?;
+        Ok(Self { identity_key, ephemeral_key, signature })
+    }
+}
+
+fn derive_ciphers(
+    shared_secret: &[u8; 32],
+    client_ephemeral: &XPublicKey,
+    server_ephemeral: &XPublicKey,
+) -> anyhow::Result<(ChaCha20Poly1305, ChaCha20Poly1305)> {
+    let hkdf = Hkdf::<Sha256>::new(None, shared_secret);
+    let mut salt = [0u8; 64];
+    salt[..32].copy_from_slice(client_ephemeral.as_bytes());
+    salt[32..].copy_from_slice(server_ephemeral.as_bytes());
+
+    let mut okm = [0u8; 64];
+    hkdf.expand_multi_info(&[b"zkcat-handshake", &salt], &mut okm)
+        .map_err(|_| anyhow::anyhow!("HKDF expansion failed"))?;
+
+    let c2s_key = Key::clone_from_slice(&okm[..32]);
+    let s2c_key = Key::clone_from_slice(&okm[32..]);
+    Ok((ChaCha20Poly1305::new(&c2s_key), ChaCha20Poly1305::new(&s2c_key)))
+}
+
+/// Runs the server side of the handshake over an already-accepted
+/// connection, returning a channel whose frames are encrypted for the
+/// client-to-server and server-to-client directions respectively.
+fn server_handshake(mut stream: TcpStream, identity: &Identity) -> anyhow::Result<SecureChannel> {
+    let mut client_bytes = [0u8; HandshakeMessage::LEN];
+    stream.read_exact(&mut client_bytes)?;
+    let client_msg = HandshakeMessage::from_bytes(&client_bytes)?;
+
+    let server_ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let server_ephemeral_public = XPublicKey::from(&server_ephemeral_secret);
+    let server_msg = HandshakeMessage::new(identity, &server_ephemeral_public);
+    stream.write_all(&server_msg.to_bytes())?;
+
+    let shared_secret = server_ephemeral_secret.diffie_hellman(&client_msg.ephemeral_key);
+    let (c2s, s2c) = derive_ciphers(shared_secret.as_bytes(), &client_msg.ephemeral_key, &server_ephemeral_public)?;
+
+    Ok(SecureChannel { stream, send_cipher: s2c, recv_cipher: c2s, send_counter: 0, recv_counter: 0 })
+}
+
+/// Runs the client side of the handshake, optionally pinning the server's
+/// long-term identity key.
+fn client_handshake(
+    mut stream: TcpStream,
+    identity: &Identity,
+    pinned_peer_key: Option<VerifyingKey>,
+) -> anyhow::Result<SecureChannel> {
+    let client_ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let client_ephemeral_public = XPublicKey::from(&client_ephemeral_secret);
+    let client_msg = HandshakeMessage::new(identity, &client_ephemeral_public);
+    stream.write_all(&client_msg.to_bytes())?;
+
+    let mut server_bytes = [0u8; HandshakeMessage::LEN];
+    stream.read_exact(&mut server_bytes)?;
+    let server_msg = HandshakeMessage::from_bytes(&server_bytes)?;
+
+    if let Some(pinned) = pinned_peer_key {
+        ensure!(server_msg.identity_key == pinned, "Server identity key does not match --peer-key");
+    }
+
+    let shared_secret = client_ephemeral_secret.diffie_hellman(&server_msg.ephemeral_key);
+    let (c2s, s2c) = derive_ciphers(shared_secret.as_bytes(), &client_ephemeral_public, &server_msg.ephemeral_key)?;
+
+    Ok(SecureChannel { stream, send_cipher: c2s, recv_cipher: s2c, send_counter: 0, recv_counter: 0 })
+}
+
+/// Accepts a single connection on `listen_addr`, performs the handshake,
+/// then sends `proof_bytes` (a serialized `ProofContainer`) followed by
+/// `redacted_content` as two encrypted frames.
+pub fn serve_once(
+    listen_addr: &str,
+    identity: &Identity,
+    proof_bytes: &[u8],
+    redacted_content: &str,
+) -> anyhow::Result<()> {
+    let listener = std::net::TcpListener::bind(listen_addr)
+        .with_context(|| format!("Failed to bind {}", listen_addr))?;
+    let (stream, peer_addr) = listener.accept().context("Failed to accept connection")?;
+    println!("Accepted connection from {}", peer_addr);
+
+    let mut channel = server_handshake(stream, identity)?;
+    channel.write_frame(proof_bytes)?;
+    channel.write_frame(redacted_content.as_bytes())?;
+    Ok(())
+}
+
+/// Connects to `addr`, performs the handshake, and returns the
+/// `(proof_bytes, redacted_content)` pair sent by the server.
+pub fn fetch_once(
+    addr: &str,
+    identity: &Identity,
+    pinned_peer_key: Option<VerifyingKey>,
+) -> anyhow::Result<(Vec<u8>, String)> {
+    let stream = TcpStream::connect(addr).with_context(|| format!("Failed to connect to {}", addr))?;
+    let mut channel = client_handshake(stream, identity, pinned_peer_key)?;
+
+    let proof_bytes = channel.read_frame()?;
+    let content_bytes = channel.read_frame()?;
+    let redacted_content = String::from_utf8(content_bytes).context("Redacted content was not valid UTF-8")?;
+    Ok((proof_bytes, redacted_content))
+}
+
+/// Parses a hex-encoded Ed25519 public key, as accepted by `--peer-key`.
+pub fn parse_peer_key(hex_str: &str) -> anyhow::Result<VerifyingKey> {
+    let bytes = hex::decode(hex_str).context("--peer-key is not valid hex")?;
+    let bytes: [u8; 32] = bytes.try_into().map_err(|_| anyhow::anyhow!("--peer-key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&bytes).context("--peer-key is not a valid Ed25519 public key")
+}
+
+pub fn fingerprint(key: &VerifyingKey) -> String {
+    hex::encode(key.as_bytes())
+}
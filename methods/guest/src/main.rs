@@ -1,25 +1,43 @@
 // methods/guest/src/main.rs
 use risc0_zkvm::guest::env;
-use sha2::{Digest, Sha256};
+
+mod merkle;
+mod redact;
+
+#[derive(serde::Deserialize)]
+struct RedactionRequest {
+    whole_lines: Vec<usize>,
+    ranges: Vec<redact::Replacement>,
+    regex_pattern: Option<String>,
+}
 
 fn main() {
-    let (full_content, redact_indices): (String, Vec<usize>) = env::read();
-    
-    let full_hash = Sha256::digest(full_content.as_bytes());
-    let full_hash_array: [u8; 32] = full_hash.into();
-    
-    let mut lines: Vec<&str> = full_content.lines().collect();
-    for &idx in &redact_indices {
-        if idx < lines.len() {
-            lines[idx] = "***REDACTED***";
-        }
-    }
-    let redacted_content = lines.join("\n");
-    
-    let redacted_hash = Sha256::digest(redacted_content.as_bytes());
-    let redacted_hash_array: [u8; 32] = redacted_hash.into();
-    
-    env::commit(&full_hash_array);
-    env::commit(&redacted_hash_array);
-    env::commit(&redact_indices);
-}
\ No newline at end of file
+    let (full_content, request): (String, RedactionRequest) = env::read();
+
+    let lines: Vec<&str> = full_content.lines().collect();
+
+    let full_leaves = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| merkle::leaf_hash(i, line))
+        .collect();
+    let full_root = merkle::root(full_leaves);
+
+    let (redacted_lines, replacements) = redact::apply(
+        &lines,
+        &request.whole_lines,
+        &request.ranges,
+        request.regex_pattern.as_deref(),
+    );
+
+    let redacted_leaves = redacted_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| merkle::leaf_hash(i, line))
+        .collect();
+    let redacted_root = merkle::root(redacted_leaves);
+
+    env::commit(&full_root);
+    env::commit(&redacted_root);
+    env::commit(&replacements);
+}
@@ -0,0 +1,44 @@
+// methods/guest/src/merkle.rs
+//! Mirrors `host/src/merkle.rs`; only root computation is needed here.
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+const LEAF_TAG: u8 = 0x00;
+const NODE_TAG: u8 = 0x01;
+
+/// `leaf_i = SHA256(0x00 ++ i_as_le_bytes ++ line_i)`.
+pub fn leaf_hash(index: usize, line: &str) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_TAG]);
+    hasher.update((index as u64).to_le_bytes());
+    hasher.update(line.as_bytes());
+    hasher.finalize().into()
+}
+
+fn parent_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Builds the tree over `leaves` and returns its root, duplicating the last
+/// node of a level when its length is odd.
+pub fn root(mut leaves: Vec<Hash>) -> Hash {
+    if leaves.is_empty() {
+        leaves.push(leaf_hash(0, ""));
+    }
+
+    while leaves.len() > 1 {
+        let mut next = Vec::with_capacity(leaves.len().div_ceil(2));
+        for pair in leaves.chunks(2) {
+            let right = pair.get(1).unwrap_or(&pair[0]);
+            next.push(parent_hash(&pair[0], right));
+        }
+        leaves = next;
+    }
+    leaves[0]
+}
@@ -0,0 +1,112 @@
+// methods/guest/src/redact.rs
+//! Mirrors `host/src/redact.rs`; runs in-guest so the host can't fake a match.
+
+use regex::Regex;
+
+pub const LINE_MARKER: &str = "***REDACTED***";
+pub const SPAN_MARKER: &str = "[REDACTED]";
+
+pub type Replacement = (usize, usize, usize);
+
+pub fn apply(
+    lines: &[&str],
+    whole_lines: &[usize],
+    ranges: &[Replacement],
+    regex_pattern: Option<&str>,
+) -> (Vec<String>, Vec<Replacement>) {
+    let compiled = regex_pattern.and_then(|p| Regex::new(p).ok());
+
+    let mut spans_by_line: Vec<Vec<(usize, usize)>> = vec![Vec::new(); lines.len()];
+
+    for &line in whole_lines {
+        if line < lines.len() {
+            spans_by_line[line].push((0, lines[line].chars().count()));
+        }
+    }
+    for &(line, start, end) in ranges {
+        if line < lines.len() {
+            let len = lines[line].chars().count();
+            if start < len {
+                spans_by_line[line].push((start, end.min(len)));
+            }
+        }
+    }
+    if let Some(re) = &compiled {
+        for (i, line) in lines.iter().enumerate() {
+            if re.is_match(line) {
+                let byte_to_char = byte_to_char_map(line);
+                for m in re.find_iter(line) {
+                    spans_by_line[i].push((byte_to_char[m.start()], byte_to_char[m.end()]));
+                }
+            }
+        }
+    }
+
+    let mut redacted_lines = Vec::with_capacity(lines.len());
+    let mut replacements = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let mut spans = std::mem::take(&mut spans_by_line[i]);
+        spans.sort_unstable();
+        let merged = merge_spans(spans);
+
+        if merged.is_empty() {
+            redacted_lines.push(line.to_string());
+            continue;
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        if merged.len() == 1 && merged[0] == (0, chars.len()) {
+            redacted_lines.push(LINE_MARKER.to_string());
+            replacements.push((i, 0, chars.len()));
+            continue;
+        }
+
+        redacted_lines.push(render_spans(&chars, &merged, SPAN_MARKER));
+        for (start, end) in merged {
+            replacements.push((i, start, end));
+        }
+    }
+
+    (redacted_lines, replacements)
+}
+
+fn render_spans(chars: &[char], spans: &[(usize, usize)], marker: &str) -> String {
+    let mut out = String::new();
+    let mut cursor = 0;
+    for &(start, end) in spans {
+        out.extend(&chars[cursor..start]);
+        out.push_str(marker);
+        cursor = end;
+    }
+    out.extend(&chars[cursor..]);
+    out
+}
+
+/// Maps every byte offset in `line` (including the one-past-the-end offset)
+/// to its char index, so byte offsets from `Regex::find_iter` can be
+/// translated into the char offsets every other span in this module uses.
+fn byte_to_char_map(line: &str) -> Vec<usize> {
+    let mut map = vec![0; line.len() + 1];
+    let mut char_index = 0;
+    for (byte_index, ch) in line.char_indices() {
+        map[byte_index] = char_index;
+        char_index += 1;
+        for offset in 1..ch.len_utf8() {
+            map[byte_index + offset] = char_index;
+        }
+    }
+    map[line.len()] = char_index;
+    map
+}
+
+fn merge_spans(spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}